@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Errors that can occur while parsing a request line, its headers, or its
+/// body.
+#[derive(Debug, PartialEq)]
+pub enum RequestError {
+    MissingMethod,
+    MissingTarget,
+    UnsupportedMethod(String),
+    MalformedHeader(String),
+    PayloadTooLarge,
+}
+
+impl RequestError {
+    /// The HTTP status code this error should be reported with.
+    pub fn status(&self) -> u16 {
+        match self {
+            RequestError::PayloadTooLarge => 413,
+            _ => 400,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::MissingMethod => write!(f, "request line is missing a method"),
+            RequestError::MissingTarget => write!(f, "request line is missing a target"),
+            RequestError::UnsupportedMethod(method) => write!(f, "unsupported method: {method}"),
+            RequestError::MalformedHeader(header) => write!(f, "malformed header: {header}"),
+            RequestError::PayloadTooLarge => write!(f, "request body exceeds the maximum size"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
@@ -1,27 +1,52 @@
+mod error;
+mod http_date;
+mod pool;
+mod response;
+mod router;
+mod static_files;
+mod url_encoding;
+
 use std::collections::HashMap;
 use std::fs;
+use std::io::ErrorKind;
 use std::process;
+use std::sync::Arc;
+use std::time::Duration;
 use std::{
     io::{prelude::*, BufReader},
     net::{TcpListener, TcpStream},
 };
 
+use error::RequestError;
+use pool::ThreadPool;
+use response::Response;
+use router::{Resolution, Router};
+
+const WORKER_THREADS: usize = 4;
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
 fn main() {
     let listener = TcpListener::bind("127.0.0.1:7878").unwrap_or_else(|err| {
         println!("{err}");
         process::exit(1);
     });
 
+    let mut router = Router::new();
+    router.add_route(Method::GET, "/", index);
+    router.add_route(Method::GET, "/static/*", static_files::serve_dir("src/static"));
+    let router = Arc::new(router);
+
+    let pool = ThreadPool::new(WORKER_THREADS);
+
     for stream in listener.incoming() {
         let stream = stream.unwrap();
-        handle_connection(stream);
+        let router = Arc::clone(&router);
+        pool.execute(move || handle_connection(stream, &router));
 
         println!("Connection established!");
     }
 }
 
-type Handler = fn() -> String;
-
 /// Gets the route from the request string, e.g. "/foo/bar?baz=qux" -> "/foo/bar"
 ///
 /// # Examples
@@ -31,16 +56,14 @@ type Handler = fn() -> String;
 /// let route = get_parsed_request(request);
 /// assert_eq!(route, "/foo/bar");
 /// ```
-fn get_parsed_request(request: &[String]) -> ParsedRequest {
+fn get_parsed_request(request: &[String]) -> Result<ParsedRequest, RequestError> {
     println!("{request:#?}");
-    let request_line = match request.first() {
-        Some(r) => r,
-        None => "",
-    };
+    let request_line = request.first().map(String::as_str).unwrap_or("");
+
     let mut parts = request_line.split_whitespace();
-    let method = parts.next().unwrap();
-    let route = parts.next().unwrap();
-    let version = parts.next().unwrap();
+    let method = parts.next().ok_or(RequestError::MissingMethod)?;
+    let route = parts.next().ok_or(RequestError::MissingTarget)?;
+    let version = parts.next().unwrap_or("HTTP/1.1");
 
     let mut route_parts = route.split('?');
     let route = route_parts.next().unwrap();
@@ -48,19 +71,11 @@ fn get_parsed_request(request: &[String]) -> ParsedRequest {
     let query = route_parts.next().unwrap_or("");
 
     let mut headers = HashMap::new();
-    for (index, header) in request.iter().enumerate() {
-        if index > 0 {
-            if !header.contains(':') || header.starts_with('{') {
-                continue;
-            }
-            let mut split = header.split(':');
-            headers.insert(
-                // key
-                split.next().unwrap().trim().to_string(),
-                // value
-                split.collect::<Vec<&str>>().join(":").trim().to_string(),
-            );
-        }
+    for header in request.iter().skip(1) {
+        let (key, value) = header
+            .split_once(':')
+            .ok_or_else(|| RequestError::MalformedHeader(header.clone()))?;
+        headers.insert(key.trim().to_string(), value.trim().to_string());
     }
 
     let method = match method {
@@ -68,35 +83,149 @@ fn get_parsed_request(request: &[String]) -> ParsedRequest {
         "POST" => Method::POST,
         "PUT" => Method::PUT,
         "DELETE" => Method::DELETE,
-        _ => Method::GET,
+        other => return Err(RequestError::UnsupportedMethod(other.to_string())),
     };
 
-    let body = match method {
-        Method::POST => match request.last() {
-            Some(r) => {
-                if r.starts_with('{') {
-                    r
-                } else {
-                    ""
-                }
-            }
-            None => "",
-        },
-        _ => "",
-    };
+    let query_params = url_encoding::parse_params(query);
 
-    ParsedRequest {
+    Ok(ParsedRequest {
         method,
         route: route.to_string(),
         version: version.to_string(),
         query: query.to_string(),
+        query_params,
         headers,
-        body: body.to_string(),
+        body: Vec::new(),
+        params: HashMap::new(),
+        form: HashMap::new(),
+    })
+}
+
+/// Reads header lines up to (but not including) the blank line that ends
+/// them. Returns `None` if the client goes idle past the keep-alive
+/// timeout, or closes the connection, before a new request line arrives.
+fn read_request_lines(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+    let mut lines = Vec::new();
+
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => return if lines.is_empty() { None } else { Some(lines) },
+            Ok(_) => {
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                if line.is_empty() {
+                    return Some(lines);
+                }
+                lines.push(line);
+            }
+            Err(err) if is_timeout(&err) => return None,
+            Err(err) => {
+                println!("{err}");
+                return None;
+            }
+        }
     }
 }
 
+fn is_timeout(err: &std::io::Error) -> bool {
+    matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+/// Largest request body (in bytes) we're willing to allocate for, whether
+/// declared via `Content-Length` or accumulated from chunks.
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Reads a request body off `reader` using `Content-Length` when present,
+/// falling back to chunked transfer decoding, and returning an empty body
+/// otherwise (e.g. a `GET` with no body). Rejects bodies above
+/// `MAX_BODY_SIZE` before allocating for them.
+fn read_body(
+    reader: &mut BufReader<TcpStream>,
+    headers: &HashMap<String, String>,
+) -> Result<Vec<u8>, RequestError> {
+    if let Some(len) = headers
+        .get("Content-Length")
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        if len > MAX_BODY_SIZE {
+            return Err(RequestError::PayloadTooLarge);
+        }
+
+        let mut body = vec![0u8; len];
+        return Ok(match reader.read_exact(&mut body) {
+            Ok(()) => body,
+            Err(err) => {
+                println!("{err}");
+                Vec::new()
+            }
+        });
+    }
+
+    let is_chunked = headers
+        .get("Transfer-Encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+
+    if is_chunked {
+        return read_chunked_body(reader);
+    }
+
+    Ok(Vec::new())
+}
+
+/// Decodes a `Transfer-Encoding: chunked` body: each chunk is a hex size
+/// line, that many payload bytes, then a trailing CRLF, until a zero-size
+/// chunk marks the end. The zero-size chunk's own trailer headers (if any)
+/// and terminating CRLF are read and discarded so a pipelined request
+/// following on the same connection isn't misparsed.
+fn read_chunked_body(reader: &mut BufReader<TcpStream>) -> Result<Vec<u8>, RequestError> {
+    let mut body = Vec::new();
+
+    loop {
+        let mut size_line = String::new();
+        if reader.read_line(&mut size_line).unwrap_or(0) == 0 {
+            break;
+        }
+
+        let size = match usize::from_str_radix(size_line.trim(), 16) {
+            Ok(size) => size,
+            Err(_) => break,
+        };
+
+        if size == 0 {
+            loop {
+                let mut trailer = String::new();
+                if reader.read_line(&mut trailer).unwrap_or(0) == 0 {
+                    break;
+                }
+                if trailer == "\r\n" || trailer == "\n" {
+                    break;
+                }
+            }
+            break;
+        }
+
+        if size > MAX_BODY_SIZE || body.len() + size > MAX_BODY_SIZE {
+            return Err(RequestError::PayloadTooLarge);
+        }
+
+        let mut chunk = vec![0u8; size];
+        if reader.read_exact(&mut chunk).is_err() {
+            break;
+        }
+        body.extend_from_slice(&chunk);
+
+        let mut trailing_crlf = [0u8; 2];
+        if reader.read_exact(&mut trailing_crlf).is_err() {
+            break;
+        }
+    }
+
+    Ok(body)
+}
+
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum Method {
     GET,
     POST,
@@ -110,74 +239,129 @@ struct ParsedRequest {
     route: String,
     version: String,
     query: String,
+    query_params: HashMap<String, String>,
     headers: HashMap<String, String>,
-    body: String,
+    body: Vec<u8>,
+    params: HashMap<String, String>,
+    form: HashMap<String, String>,
 }
 
-/// Handles a connection, reading the request and writing the response.
-fn handle_connection(mut stream: TcpStream) {
-    let mut reader = BufReader::new(&stream);
-    let mut req: Vec<String> = reader
-        .by_ref()
-        .lines()
-        .map(|result| result.unwrap())
-        .take_while(|line| !line.is_empty())
-        .collect();
-
-    match req[0].contains("POST") {
-        true => {
-            println!("POST request");
-            let mut contents_raw: Vec<u8> = vec![];
-            reader.read_until(b'}', &mut contents_raw).unwrap();
-            req.push(String::from_utf8(contents_raw).unwrap());
-        }
-        false => {
-            println!("GET request");
+/// Handles a connection, serving requests on it until the client asks to
+/// close, goes idle past the keep-alive timeout, or the connection drops.
+fn handle_connection(mut stream: TcpStream, router: &Router) {
+    if let Err(err) = stream.set_read_timeout(Some(KEEP_ALIVE_TIMEOUT)) {
+        println!("{err}");
+    }
+
+    let read_stream = match stream.try_clone() {
+        Ok(read_stream) => read_stream,
+        Err(err) => {
+            println!("{err}");
+            return;
         }
     };
+    let mut reader = BufReader::new(read_stream);
 
-    let request_route = get_parsed_request(&req);
+    loop {
+        let req = match read_request_lines(&mut reader) {
+            Some(req) => req,
+            None => {
+                let response = Response::status(408).header("Connection", "close");
+                if let Err(err) = response.write_to(&mut stream) {
+                    println!("{err}");
+                }
+                return;
+            }
+        };
 
-    println!("Request Route {request_route:#?}");
+        let mut request_route = match get_parsed_request(&req) {
+            Ok(r) => r,
+            Err(err) => {
+                let response = Response::status(err.status())
+                    .header("Connection", "close")
+                    .body(err.to_string().into_bytes());
+                if let Err(err) = response.write_to(&mut stream) {
+                    println!("{err}");
+                }
+                return;
+            }
+        };
+        request_route.body = match read_body(&mut reader, &request_route.headers) {
+            Ok(body) => body,
+            Err(err) => {
+                let response = Response::status(err.status())
+                    .header("Connection", "close")
+                    .body(err.to_string().into_bytes());
+                if let Err(err) = response.write_to(&mut stream) {
+                    println!("{err}");
+                }
+                return;
+            }
+        };
 
-    let mut handlers: HashMap<&str, Handler> = HashMap::new();
-    handlers.insert("/", index);
-    let mut status = "HTTP/1.1 200 OK \r\n";
-    let handler = match handlers.get(request_route.route.as_str()) {
-        Some(h) => h.to_owned(),
-        None => {
-            println!("Request {req:#?}");
-            status = "HTTP/1.1 404 Not Found \r\n";
-            not_found
+        let is_form_encoded = request_route
+            .headers
+            .get("Content-Type")
+            .map(|content_type| content_type.starts_with("application/x-www-form-urlencoded"))
+            .unwrap_or(false);
+        if is_form_encoded {
+            let body = String::from_utf8_lossy(&request_route.body).into_owned();
+            request_route.form = url_encoding::parse_params(&body);
         }
-    };
 
-    let body = handler();
-    let response = format!("{status}Content-Length: {}\r\n\r\n{body}", body.len());
-    match stream.write_all(response.as_bytes()) {
-        Ok(r) => r,
-        Err(err) => {
+        println!("Request Route {request_route:#?}");
+
+        let keep_alive = !request_route
+            .headers
+            .get("Connection")
+            .map(|connection| connection.eq_ignore_ascii_case("close"))
+            .unwrap_or(false);
+
+        let response = match router.resolve(&request_route.method, &request_route.route) {
+            Resolution::Matched { handler, params } => {
+                request_route.params = params;
+                (*handler)(&request_route)
+            }
+            Resolution::MethodNotAllowed => Response::status(405),
+            Resolution::NotFound => {
+                println!("Request {req:#?}");
+                not_found(&request_route)
+            }
+        };
+        let response = response.header(
+            "Connection",
+            if keep_alive { "keep-alive" } else { "close" },
+        );
+
+        if let Err(err) = response.write_to(&mut stream) {
             println!("{err}");
+            return;
+        }
+
+        if !keep_alive {
+            return;
         }
     }
 }
 
-fn index() -> String {
-    match fs::read_to_string("src/index.html") {
-        Ok(r) => r,
+fn index(_req: &ParsedRequest) -> Response {
+    match fs::read("src/index.html") {
+        Ok(body) => Response::ok().header("Content-Type", "text/html").body(body),
         Err(err) => {
             println!("{err}");
-            "".to_string()
+            Response::status(500)
         }
     }
 }
 
-fn not_found() -> String {
-    match fs::read_to_string("src/404.html") {
-        Ok(r) => r,
+fn not_found(_req: &ParsedRequest) -> Response {
+    match fs::read("src/404.html") {
+        Ok(body) => Response::status(404)
+            .header("Content-Type", "text/html")
+            .body(body),
         Err(err) => {
             println!("{err}");
-            "".to_string()
+            Response::status(404)
         }
     }
 }
@@ -189,26 +373,152 @@ mod tests {
     #[test]
     fn test_get_parsed_request() {
         let request = vec!["GET / HTTP/1.1".to_string()];
-        let parsed = get_parsed_request(&request);
+        let parsed = get_parsed_request(&request).unwrap();
         assert_eq!(parsed.route, "/");
 
         let request = vec!["GET /foo HTTP/1.1".to_string()];
-        let parsed = get_parsed_request(&request);
+        let parsed = get_parsed_request(&request).unwrap();
         assert_eq!(parsed.route, "/foo");
 
         let request = vec!["GET /foo/bar HTTP/1.1".to_string()];
-        let parsed = get_parsed_request(&request);
+        let parsed = get_parsed_request(&request).unwrap();
         assert_eq!(parsed.route, "/foo/bar");
 
         let request = vec!["GET /foo/bar?baz=qux HTTP/1.1".to_string()];
-        let parsed = get_parsed_request(&request);
+        let parsed = get_parsed_request(&request).unwrap();
         assert_eq!(parsed.route, "/foo/bar");
 
         let request = vec![
             "GET /foo/bar?baz=qux HTTP/1.1".to_string(),
             "Host: localhost:7878".to_string(),
         ];
-        let parsed = get_parsed_request(&request);
+        let parsed = get_parsed_request(&request).unwrap();
         assert_eq!(parsed.headers.get("Host").unwrap(), "localhost:7878");
+
+        let request = vec!["GET /foo/bar?baz=qux+value HTTP/1.1".to_string()];
+        let parsed = get_parsed_request(&request).unwrap();
+        assert_eq!(parsed.query_params.get("baz").unwrap(), "qux value");
+    }
+
+    #[test]
+    fn test_get_parsed_request_errors() {
+        let request: Vec<String> = vec![];
+        assert_eq!(
+            get_parsed_request(&request).unwrap_err(),
+            RequestError::MissingMethod
+        );
+
+        let request = vec!["GET".to_string()];
+        assert_eq!(
+            get_parsed_request(&request).unwrap_err(),
+            RequestError::MissingTarget
+        );
+
+        let request = vec!["PATCH / HTTP/1.1".to_string()];
+        assert_eq!(
+            get_parsed_request(&request).unwrap_err(),
+            RequestError::UnsupportedMethod("PATCH".to_string())
+        );
+
+        let request = vec!["GET / HTTP/1.1".to_string(), "Host localhost".to_string()];
+        assert_eq!(
+            get_parsed_request(&request).unwrap_err(),
+            RequestError::MalformedHeader("Host localhost".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_body_content_length_and_chunked() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"abc").unwrap();
+            stream.write_all(b"3\r\ndef\r\n0\r\n\r\n").unwrap();
+        });
+
+        let (server_stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(server_stream);
+
+        let mut headers = HashMap::new();
+        headers.insert("Content-Length".to_string(), "3".to_string());
+        assert_eq!(read_body(&mut reader, &headers).unwrap(), b"abc".to_vec());
+
+        let mut headers = HashMap::new();
+        headers.insert("Transfer-Encoding".to_string(), "chunked".to_string());
+        assert_eq!(read_body(&mut reader, &headers).unwrap(), b"def".to_vec());
+
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn test_read_body_rejects_oversized_content_length() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = std::thread::spawn(move || {
+            let _stream = TcpStream::connect(addr).unwrap();
+        });
+
+        let (server_stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(server_stream);
+
+        let mut headers = HashMap::new();
+        headers.insert("Content-Length".to_string(), "18446744073709551615".to_string());
+        assert_eq!(
+            read_body(&mut reader, &headers).unwrap_err(),
+            RequestError::PayloadTooLarge
+        );
+
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn test_chunked_body_consumes_trailing_crlf_for_pipelined_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .write_all(b"3\r\ndef\r\n0\r\n\r\nGET / HTTP/1.1\r\n\r\n")
+                .unwrap();
+        });
+
+        let (server_stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(server_stream);
+
+        let mut headers = HashMap::new();
+        headers.insert("Transfer-Encoding".to_string(), "chunked".to_string());
+        assert_eq!(read_body(&mut reader, &headers).unwrap(), b"def".to_vec());
+
+        assert_eq!(
+            read_request_lines(&mut reader).unwrap(),
+            vec!["GET / HTTP/1.1".to_string()]
+        );
+
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn test_read_request_lines_times_out_when_idle() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = std::thread::spawn(move || {
+            let _stream = TcpStream::connect(addr).unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+        });
+
+        let (server_stream, _) = listener.accept().unwrap();
+        server_stream
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let mut reader = BufReader::new(server_stream);
+
+        assert_eq!(read_request_lines(&mut reader), None);
+
+        client.join().unwrap();
     }
 }
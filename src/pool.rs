@@ -0,0 +1,133 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+enum Message {
+    NewJob(Job),
+    Terminate,
+}
+
+/// A fixed-size pool of worker threads that pull jobs off a shared queue.
+///
+/// # Examples
+///
+/// ```
+/// let pool = ThreadPool::new(4);
+/// pool.execute(|| println!("running on a worker thread"));
+/// ```
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: mpsc::Sender<Message>,
+}
+
+impl ThreadPool {
+    /// Creates a new `ThreadPool`.
+    ///
+    /// # Panics
+    ///
+    /// `new` panics if `size` is zero, since a pool with no workers could
+    /// never make progress on submitted jobs.
+    pub fn new(size: usize) -> ThreadPool {
+        assert!(size > 0);
+
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let mut workers = Vec::with_capacity(size);
+        for id in 0..size {
+            workers.push(Worker::new(id, Arc::clone(&receiver)));
+        }
+
+        ThreadPool { workers, sender }
+    }
+
+    pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let job = Box::new(f);
+        self.sender.send(Message::NewJob(job)).unwrap();
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        for _ in &self.workers {
+            self.sender.send(Message::Terminate).unwrap();
+        }
+
+        for worker in &mut self.workers {
+            println!("Shutting down worker {}", worker.id);
+
+            if let Some(thread) = worker.thread.take() {
+                thread.join().unwrap();
+            }
+        }
+    }
+}
+
+struct Worker {
+    id: usize,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Message>>>) -> Worker {
+        let thread = thread::spawn(move || loop {
+            let message = receiver.lock().unwrap().recv().unwrap();
+
+            match message {
+                Message::NewJob(job) => {
+                    println!("Worker {id} got a job; executing.");
+                    // Catch a panicking job so it can't unwind this worker's
+                    // thread and permanently shrink the pool's capacity.
+                    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                        println!("Worker {id} job panicked: {}", panic_message(&payload));
+                    }
+                }
+                Message::Terminate => {
+                    println!("Worker {id} was told to terminate.");
+                    break;
+                }
+            }
+        });
+
+        Worker {
+            id,
+            thread: Some(thread),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pool_survives_panicking_job() {
+        let pool = ThreadPool::new(2);
+        let (tx, rx) = mpsc::channel();
+
+        pool.execute(|| panic!("boom"));
+
+        pool.execute(move || {
+            tx.send(()).unwrap();
+        });
+
+        rx.recv_timeout(Duration::from_secs(1))
+            .expect("pool should still run jobs after a prior job panicked");
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::TcpStream;
+
+/// An HTTP response assembled by a handler.
+///
+/// # Examples
+///
+/// ```
+/// let response = Response::ok()
+///     .header("Content-Type", "text/plain")
+///     .body(b"hello".to_vec());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn status(status: u16) -> Response {
+        Response {
+            status,
+            headers: HashMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn ok() -> Response {
+        Response::status(200)
+    }
+
+    pub fn header(mut self, key: &str, value: &str) -> Response {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn body(mut self, body: Vec<u8>) -> Response {
+        self.body = body;
+        self
+    }
+
+    /// Serializes this response into the HTTP/1.1 wire format and writes it
+    /// to `stream`, filling in `Content-Length` from the body.
+    pub fn write_to(&self, stream: &mut TcpStream) -> std::io::Result<()> {
+        let mut wire = format!(
+            "HTTP/1.1 {} {}\r\n",
+            self.status,
+            reason_phrase(self.status)
+        );
+
+        for (key, value) in &self.headers {
+            wire.push_str(&format!("{key}: {value}\r\n"));
+        }
+        wire.push_str(&format!("Content-Length: {}\r\n\r\n", self.body.len()));
+
+        stream.write_all(wire.as_bytes())?;
+        stream.write_all(&self.body)
+    }
+}
+
+/// Maps a status code to its standard reason phrase.
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let response = Response::ok().header("X-Test", "1").body(b"hi".to_vec());
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers.get("X-Test").unwrap(), "1");
+        assert_eq!(response.body, b"hi".to_vec());
+    }
+
+    #[test]
+    fn test_reason_phrase() {
+        assert_eq!(reason_phrase(200), "OK");
+        assert_eq!(reason_phrase(404), "Not Found");
+        assert_eq!(reason_phrase(999), "Unknown");
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{Method, ParsedRequest, Response};
+
+pub type Handler = Arc<dyn Fn(&ParsedRequest) -> Response + Send + Sync>;
+
+/// Outcome of matching an incoming method/route pair against the table.
+pub enum Resolution {
+    Matched {
+        handler: Handler,
+        params: HashMap<String, String>,
+    },
+    /// No registered pattern matches the route at all.
+    NotFound,
+    /// A pattern matches the route, but not for this method.
+    MethodNotAllowed,
+}
+
+/// Routes `(Method, pattern)` pairs to handlers. A pattern segment written
+/// as `:name` matches any single incoming segment and is captured under
+/// that name; a trailing `*` segment matches every remaining segment,
+/// joined by `/`, so one route can serve a whole directory tree.
+///
+/// # Examples
+///
+/// ```
+/// let mut router = Router::new();
+/// router.add_route(Method::GET, "/users/:id", show_user);
+/// ```
+pub struct Router {
+    routes: HashMap<(Method, String), Handler>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router {
+            routes: HashMap::new(),
+        }
+    }
+
+    pub fn add_route<H>(&mut self, method: Method, pattern: &str, handler: H)
+    where
+        H: Fn(&ParsedRequest) -> Response + Send + Sync + 'static,
+    {
+        self.routes
+            .insert((method, pattern.to_string()), Arc::new(handler));
+    }
+
+    /// Matches `route` segment-by-segment against every registered pattern
+    /// for any method, so a path match with the wrong method can still be
+    /// reported as 405 instead of 404.
+    pub fn resolve(&self, method: &Method, route: &str) -> Resolution {
+        let incoming: Vec<&str> = route.split('/').collect();
+        let mut path_matched = false;
+
+        for ((route_method, pattern), handler) in &self.routes {
+            let pattern_parts: Vec<&str> = pattern.split('/').collect();
+
+            let params = match match_pattern(&pattern_parts, &incoming) {
+                Some(params) => params,
+                None => continue,
+            };
+
+            path_matched = true;
+
+            if route_method == method {
+                return Resolution::Matched {
+                    handler: Arc::clone(handler),
+                    params,
+                };
+            }
+        }
+
+        if path_matched {
+            Resolution::MethodNotAllowed
+        } else {
+            Resolution::NotFound
+        }
+    }
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Matches `incoming` segments against `pattern_parts`, capturing `:name`
+/// segments and, if `pattern_parts` ends with a bare `*`, the remaining
+/// segments under the `"*"` key.
+fn match_pattern(pattern_parts: &[&str], incoming: &[&str]) -> Option<HashMap<String, String>> {
+    let is_wildcard = pattern_parts.last() == Some(&"*");
+    let fixed_len = if is_wildcard {
+        pattern_parts.len() - 1
+    } else {
+        pattern_parts.len()
+    };
+
+    if is_wildcard {
+        if incoming.len() < fixed_len {
+            return None;
+        }
+    } else if pattern_parts.len() != incoming.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    for (pattern_part, incoming_part) in pattern_parts[..fixed_len].iter().zip(incoming.iter()) {
+        if let Some(name) = pattern_part.strip_prefix(':') {
+            params.insert(name.to_string(), incoming_part.to_string());
+        } else if pattern_part != incoming_part {
+            return None;
+        }
+    }
+
+    if is_wildcard {
+        params.insert("*".to_string(), incoming[fixed_len..].join("/"));
+    }
+
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder(_req: &ParsedRequest) -> Response {
+        Response::ok()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/users/:id", placeholder);
+        router.add_route(Method::POST, "/users/:id", placeholder);
+
+        match router.resolve(&Method::GET, "/users/42") {
+            Resolution::Matched { params, .. } => {
+                assert_eq!(params.get("id").unwrap(), "42");
+            }
+            _ => panic!("expected a match"),
+        }
+
+        assert!(matches!(
+            router.resolve(&Method::DELETE, "/users/42"),
+            Resolution::MethodNotAllowed
+        ));
+
+        assert!(matches!(
+            router.resolve(&Method::GET, "/posts/42"),
+            Resolution::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_resolve_wildcard() {
+        let mut router = Router::new();
+        router.add_route(Method::GET, "/assets/*", placeholder);
+
+        match router.resolve(&Method::GET, "/assets/css/app.css") {
+            Resolution::Matched { params, .. } => {
+                assert_eq!(params.get("*").unwrap(), "css/app.css");
+            }
+            _ => panic!("expected a match"),
+        }
+    }
+}
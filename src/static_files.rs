@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::http_date;
+use crate::{ParsedRequest, Response};
+
+/// Builds a handler that serves files from under `root`, guarding against
+/// path traversal, picking a `Content-Type` from the file extension, and
+/// answering conditional `If-Modified-Since` requests with `304`.
+///
+/// Intended to be registered against a trailing-wildcard pattern so nested
+/// paths reach it, e.g. `router.add_route(Method::GET, "/static/*", serve_dir("assets"))`.
+pub fn serve_dir(
+    root: impl Into<PathBuf>,
+) -> impl Fn(&ParsedRequest) -> Response + Send + Sync + Clone {
+    let root = root.into();
+    move |req: &ParsedRequest| serve(&root, req)
+}
+
+fn serve(root: &Path, req: &ParsedRequest) -> Response {
+    let requested = req
+        .params
+        .get("*")
+        .map(String::as_str)
+        .unwrap_or_else(|| req.route.trim_start_matches('/'));
+    let requested = requested.trim_start_matches('/');
+
+    if requested.split('/').any(|segment| segment == "..") {
+        return Response::status(400).body(b"invalid path".to_vec());
+    }
+
+    let root = match root.canonicalize() {
+        Ok(root) => root,
+        Err(_) => return Response::status(404),
+    };
+    let path = match root.join(requested).canonicalize() {
+        Ok(path) => path,
+        Err(_) => return Response::status(404),
+    };
+    if !path.starts_with(&root) {
+        return Response::status(404);
+    }
+
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(_) => return Response::status(404),
+    };
+    let modified = match metadata.modified() {
+        Ok(modified) => modified,
+        Err(_) => return Response::status(404),
+    };
+
+    if let Some(since) = req
+        .headers
+        .get("If-Modified-Since")
+        .and_then(|header| http_date::parse(header))
+    {
+        if modified <= since {
+            return Response::status(304);
+        }
+    }
+
+    let body = match fs::read(&path) {
+        Ok(body) => body,
+        Err(_) => return Response::status(404),
+    };
+
+    Response::ok()
+        .header("Content-Type", content_type(&path))
+        .header("Last-Modified", &http_date::format(modified))
+        .body(body)
+}
+
+/// Guesses a `Content-Type` from the file extension, defaulting to a
+/// generic binary type for anything unrecognized.
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") | Some("htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request_for(route: &str, headers: HashMap<String, String>) -> ParsedRequest {
+        ParsedRequest {
+            method: crate::Method::GET,
+            route: route.to_string(),
+            version: "HTTP/1.1".to_string(),
+            query: String::new(),
+            query_params: HashMap::new(),
+            headers,
+            body: Vec::new(),
+            params: HashMap::new(),
+            form: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(content_type(Path::new("app.css")), "text/css");
+        assert_eq!(content_type(Path::new("data.bin")), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_serve_rejects_path_traversal() {
+        let handler = serve_dir("src");
+        let req = request_for("/../Cargo.toml", HashMap::new());
+        assert_eq!(handler(&req).status, 400);
+    }
+
+    /// Creates a fresh, uniquely-named temp directory for a test to serve
+    /// files from, so parallel test runs don't collide.
+    fn temp_dir_for(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spores_static_files_test_{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_serve_returns_file_contents() {
+        let dir = temp_dir_for("serve");
+        fs::write(dir.join("hello.css"), b"body { color: red; }").unwrap();
+
+        let handler = serve_dir(dir.clone());
+        let req = request_for("/hello.css", HashMap::new());
+        let response = handler(&req);
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers.get("Content-Type").unwrap(), "text/css");
+        assert!(response.headers.contains_key("Last-Modified"));
+        assert_eq!(response.body, b"body { color: red; }".to_vec());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_serve_returns_304_when_not_modified_since() {
+        let dir = temp_dir_for("conditional");
+        let file = dir.join("hello.txt");
+        fs::write(&file, b"hi").unwrap();
+        let modified = fs::metadata(&file).unwrap().modified().unwrap();
+
+        let mut headers = HashMap::new();
+        headers.insert(
+            "If-Modified-Since".to_string(),
+            http_date::format(modified + std::time::Duration::from_secs(1)),
+        );
+
+        let handler = serve_dir(dir.clone());
+        let req = request_for("/hello.txt", headers);
+        let response = handler(&req);
+
+        assert_eq!(response.status, 304);
+        assert!(response.body.is_empty());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
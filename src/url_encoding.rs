@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+/// Decodes a `application/x-www-form-urlencoded` string: `+` becomes a
+/// space and `%XX` hex escapes become their byte value. A malformed escape
+/// (missing or non-hex digits) is left in the output literally rather than
+/// causing a panic.
+pub fn decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hi = (bytes[i + 1] as char).to_digit(16).unwrap();
+                let lo = (bytes[i + 2] as char).to_digit(16).unwrap();
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parses a query string or `application/x-www-form-urlencoded` body into
+/// a key/value map, splitting on `&` then `=` and percent-decoding both
+/// sides.
+pub fn parse_params(input: &str) -> HashMap<String, String> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = decode(parts.next().unwrap_or(""));
+            let value = decode(parts.next().unwrap_or(""));
+            (key, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("foo+bar"), "foo bar");
+        assert_eq!(decode("a%20b"), "a b");
+        assert_eq!(decode("100%25"), "100%");
+        assert_eq!(decode("bad%2"), "bad%2");
+        assert_eq!(decode("bad%zz"), "bad%zz");
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let params = parse_params("baz=qux&name=John+Doe&empty=");
+        assert_eq!(params.get("baz").unwrap(), "qux");
+        assert_eq!(params.get("name").unwrap(), "John Doe");
+        assert_eq!(params.get("empty").unwrap(), "");
+    }
+}